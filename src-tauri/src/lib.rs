@@ -1,12 +1,21 @@
 use serde::{Deserialize, Serialize};
 use sqlx::{sqlite::SqliteConnectOptions, SqlitePool};
+use std::io::{BufRead, BufReader, Read};
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::process::{Command, Stdio};
 use std::str::FromStr;
-use tauri::{Manager, State};
+use std::time::Duration;
+use tauri::{Emitter, Manager, State, Window};
 
 const SCHEMA_SQL: &str = include_str!("../sql/schema.sql");
 
+const GEMINI_URL: &str =
+  "https://generativelanguage.googleapis.com/v1beta/models/gemini-2.5-flash:generateContent";
+const GEMINI_REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+const GEMINI_MAX_RETRIES: u32 = 3;
+const GEMINI_BACKOFF_BASE: Duration = Duration::from_millis(500);
+const GEMINI_BACKOFF_CAP: Duration = Duration::from_secs(8);
+
 #[derive(Clone)]
 struct Db(SqlitePool);
 
@@ -14,10 +23,40 @@ struct Db(SqlitePool);
 struct VibeEditResult {
   output_path: String,
   filters: Vec<String>,
+  filter_graph: Vec<FilterSpec>,
   used_gemini: bool,
   trial_watermark: bool,
 }
 
+#[derive(Clone, Serialize)]
+struct FilterSpec {
+  name: String,
+  args: Vec<(String, String)>,
+}
+
+#[derive(Serialize)]
+struct HighlightResult {
+  output_path: String,
+  segments: Vec<(f64, f64)>,
+  trial_watermark: bool,
+}
+
+#[derive(Clone, Serialize)]
+struct VibeEditProgress {
+  phase: String,
+  percent: f64,
+}
+
+#[derive(Serialize, sqlx::FromRow)]
+struct ProjectRecord {
+  id: i64,
+  input_path: String,
+  output_path: String,
+  prompt: String,
+  tags: String,
+  created_at: String,
+}
+
 #[derive(Deserialize)]
 struct GeminiResponse {
   candidates: Vec<GeminiCandidate>,
@@ -46,6 +85,187 @@ fn ensure_three_filters(mut filters: Vec<String>) -> Vec<String> {
   filters
 }
 
+fn split_filter_args(rest: &str) -> Vec<String> {
+  let mut parts = Vec::new();
+  let mut current = String::new();
+  let mut in_quotes = false;
+  for c in rest.chars() {
+    match c {
+      '\'' => {
+        in_quotes = !in_quotes;
+        current.push(c);
+      }
+      ':' if !in_quotes => parts.push(std::mem::take(&mut current)),
+      _ => current.push(c),
+    }
+  }
+  parts.push(current);
+  parts.into_iter().filter(|p| !p.is_empty()).collect()
+}
+
+fn strip_quotes(v: &str) -> String {
+  let v = v.trim();
+  if v.len() >= 2 && v.starts_with('\'') && v.ends_with('\'') {
+    v[1..v.len() - 1].to_string()
+  } else {
+    v.to_string()
+  }
+}
+
+fn parse_filter_spec(raw: &str) -> FilterSpec {
+  let (name, rest) = raw.split_once('=').unwrap_or((raw, ""));
+  let args = split_filter_args(rest)
+    .into_iter()
+    .enumerate()
+    .map(|(i, chunk)| match chunk.split_once('=') {
+      Some((k, v)) => (k.to_string(), strip_quotes(v)),
+      None => (format!("_{}", i), strip_quotes(&chunk)),
+    })
+    .collect();
+  FilterSpec { name: name.to_string(), args }
+}
+
+fn render_filter_spec(spec: &FilterSpec) -> String {
+  if spec.args.is_empty() {
+    return spec.name.clone();
+  }
+  let rendered = spec
+    .args
+    .iter()
+    .map(|(k, v)| {
+      if k.starts_with('_') {
+        v.clone()
+      } else if k == "text" {
+        format!("text='{}'", v)
+      } else {
+        format!("{}={}", k, v)
+      }
+    })
+    .collect::<Vec<_>>()
+    .join(":");
+  format!("{}={}", spec.name, rendered)
+}
+
+fn clamp_f64(raw: &str, min: f64, max: f64, default: f64) -> String {
+  let v = raw.trim().parse::<f64>().unwrap_or(default).clamp(min, max);
+  if v.fract() == 0.0 {
+    format!("{}", v as i64)
+  } else {
+    format!("{}", v)
+  }
+}
+
+fn strip_disallowed(v: &str, allowed_extra: &str) -> String {
+  v.chars()
+    .filter(|c| c.is_ascii_alphanumeric() || allowed_extra.contains(*c))
+    .collect()
+}
+
+fn sanitize_expr(v: &str) -> Option<String> {
+  let cleaned = strip_disallowed(v, ".-+*/()");
+  if cleaned.is_empty() {
+    None
+  } else {
+    Some(cleaned)
+  }
+}
+
+fn validate_filter_spec(spec: FilterSpec) -> Result<FilterSpec, String> {
+  let FilterSpec { name, args } = spec;
+  let args = match name.as_str() {
+    "hue" => args
+      .into_iter()
+      .filter_map(|(k, v)| match k.as_str() {
+        "h" => Some((k, clamp_f64(&v, -360.0, 360.0, 0.0))),
+        "s" => Some((k, clamp_f64(&v, 0.0, 3.0, 1.0))),
+        "b" => Some((k, clamp_f64(&v, -10.0, 10.0, 0.0))),
+        _ => None,
+      })
+      .collect(),
+    "eq" => args
+      .into_iter()
+      .filter_map(|(k, v)| match k.as_str() {
+        "brightness" => Some((k, clamp_f64(&v, -1.0, 1.0, 0.0))),
+        "contrast" => Some((k, clamp_f64(&v, 0.0, 3.0, 1.0))),
+        "saturation" => Some((k, clamp_f64(&v, 0.0, 3.0, 1.0))),
+        "gamma" => Some((k, clamp_f64(&v, 0.1, 10.0, 1.0))),
+        _ => None,
+      })
+      .collect(),
+    "setpts" => {
+      let factor = args.get(0).map(|(_, v)| v.as_str()).unwrap_or("1.0*PTS");
+      let numeric = factor.split('*').next().unwrap_or("1.0");
+      vec![("_0".to_string(), format!("{}*PTS", clamp_f64(numeric, 0.1, 4.0, 1.0)))]
+    }
+    "scale" => args
+      .into_iter()
+      .filter_map(|(k, v)| match k.as_str() {
+        "w" | "h" => sanitize_expr(&v).map(|v| (k, v)),
+        _ if k.starts_with('_') => sanitize_expr(&v).map(|v| (k, v)),
+        _ => None,
+      })
+      .collect(),
+    "fade" => args
+      .into_iter()
+      .filter_map(|(k, v)| match k.as_str() {
+        "t" if v == "in" || v == "out" => Some((k, v)),
+        "st" => Some((k, clamp_f64(&v, 0.0, 86_400.0, 0.0))),
+        "d" => Some((k, clamp_f64(&v, 0.0, 60.0, 1.0))),
+        _ => None,
+      })
+      .collect(),
+    "drawtext" => args
+      .into_iter()
+      .filter_map(|(k, v)| match k.as_str() {
+        "text" => {
+          let cleaned = strip_disallowed(&v.replace(':', " "), " _-.!?");
+          if cleaned.is_empty() {
+            None
+          } else {
+            Some((k, cleaned))
+          }
+        }
+        "x" | "y" => sanitize_expr(&v).map(|v| (k, v)),
+        "fontcolor" => {
+          let cleaned = strip_disallowed(&v, "#@.");
+          if cleaned.is_empty() {
+            None
+          } else {
+            Some((k, cleaned))
+          }
+        }
+        "fontfile" => {
+          let cleaned = strip_disallowed(&v, "._-/");
+          if cleaned.is_empty() {
+            None
+          } else {
+            Some((k, cleaned))
+          }
+        }
+        "fontsize" => Some((k, clamp_f64(&v, 8.0, 120.0, 24.0))),
+        _ => None,
+      })
+      .collect(),
+    other => return Err(format!("filter '{}' is not on the allowlist", other)),
+  };
+  Ok(FilterSpec { name, args })
+}
+
+fn sanitize_filters(raw: Vec<String>) -> Vec<FilterSpec> {
+  let mut graph: Vec<FilterSpec> = raw
+    .into_iter()
+    .map(|f| parse_filter_spec(&f))
+    .filter_map(|spec| validate_filter_spec(spec).ok())
+    .collect();
+  if graph.is_empty() {
+    graph.push(FilterSpec {
+      name: "hue".to_string(),
+      args: vec![("s".to_string(), "1".to_string())],
+    });
+  }
+  graph
+}
+
 fn drawtext_font() -> &'static str {
   #[cfg(target_os = "macos")]
   return "fontfile=/System/Library/Fonts/Supplemental/Arial.ttf";
@@ -80,6 +300,69 @@ fn video_duration_seconds(path: &Path) -> Result<f64, String> {
   s.trim().parse::<f64>().map_err(|_| "invalid duration".to_string())
 }
 
+fn out_time_percent(out_time_us: f64, total_duration_secs: f64) -> Option<f64> {
+  if total_duration_secs <= 0.0 {
+    return None;
+  }
+  Some(((out_time_us / 1_000_000.0) / total_duration_secs * 100.0).clamp(0.0, 100.0))
+}
+
+fn spawn_ffmpeg_with_progress(
+  mut cmd: Command,
+  total_duration_secs: f64,
+  window: &Window,
+  phase: &str,
+) -> Result<(), String> {
+  cmd.arg("-progress").arg("pipe:1").arg("-nostats");
+  let mut child = cmd
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .spawn()
+    .map_err(|e| e.to_string())?;
+
+  let stderr = child.stderr.take().ok_or_else(|| "failed to capture ffmpeg stderr".to_string())?;
+  let stderr_reader = std::thread::spawn(move || {
+    let mut buf = String::new();
+    let _ = BufReader::new(stderr).read_to_string(&mut buf);
+    buf
+  });
+
+  let stdout = child.stdout.take().ok_or_else(|| "failed to capture ffmpeg stdout".to_string())?;
+  for line in BufReader::new(stdout).lines() {
+    let line = line.map_err(|e| e.to_string())?;
+    let Some((key, value)) = line.split_once('=') else {
+      continue;
+    };
+    let percent = match key {
+      "out_time_ms" => value
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .and_then(|us| out_time_percent(us, total_duration_secs)),
+      "progress" if value.trim() == "end" => Some(100.0),
+      _ => None,
+    };
+    if let Some(percent) = percent {
+      let _ = window.emit("vibe_edit_progress", VibeEditProgress { phase: phase.to_string(), percent });
+    }
+  }
+
+  let status = child.wait().map_err(|e| e.to_string())?;
+  let stderr_buf = stderr_reader.join().unwrap_or_default();
+  if !status.success() {
+    return Err(if stderr_buf.is_empty() {
+      format!("FFmpeg failed during {}", phase)
+    } else {
+      format!(
+        "FFmpeg failed during {}: {}",
+        phase,
+        stderr_buf.lines().take(5).collect::<Vec<_>>().join(" ")
+      )
+    });
+  }
+  Ok(())
+}
+
 fn wants_overlay(prompt: &str) -> bool {
   let p = prompt.to_lowercase();
   p.contains("add animation")
@@ -88,6 +371,163 @@ fn wants_overlay(prompt: &str) -> bool {
     || p.contains("overlay")
 }
 
+fn derive_tags(prompt: &str, used_gemini: bool, trial_watermark: bool, overlay: bool) -> Vec<String> {
+  let p = prompt.to_lowercase();
+  let mut tags = Vec::new();
+  if p.contains("energetic") || p.contains("fast") {
+    tags.push("energetic".to_string());
+  }
+  if p.contains("chill") || p.contains("calm") {
+    tags.push("chill".to_string());
+  }
+  if overlay {
+    tags.push("overlay".to_string());
+  }
+  if trial_watermark {
+    tags.push("watermarked".to_string());
+  }
+  tags.push(if used_gemini { "gemini" } else { "fallback" }.to_string());
+  tags
+}
+
+const BEAT_SAMPLE_RATE: u32 = 22050;
+const BEAT_WINDOW_SAMPLES: usize = 1024;
+const BEAT_SMOOTH_RADIUS: usize = 2;
+const BEAT_THRESHOLD_MULT: f64 = 1.5;
+const BEAT_MIN_SPACING_SECS: f64 = 0.2;
+
+fn decode_mono_pcm(path: &Path) -> Result<Vec<f32>, String> {
+  let out = Command::new("ffmpeg")
+    .arg("-i")
+    .arg(path)
+    .args(["-f", "f32le", "-ac", "1", "-ar", &BEAT_SAMPLE_RATE.to_string(), "-"])
+    .output()
+    .map_err(|e| e.to_string())?;
+  if !out.status.success() {
+    return Err(String::from_utf8_lossy(&out.stderr).to_string());
+  }
+  Ok(
+    out
+      .stdout
+      .chunks_exact(4)
+      .map(|c| f32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+      .collect(),
+  )
+}
+
+fn rms_envelope(samples: &[f32], window: usize) -> Vec<f32> {
+  samples
+    .chunks(window)
+    .map(|w| {
+      let sum_sq: f64 = w.iter().map(|&s| (s as f64) * (s as f64)).sum();
+      (sum_sq / w.len() as f64).sqrt() as f32
+    })
+    .collect()
+}
+
+fn smooth_envelope(envelope: &[f32], radius: usize) -> Vec<f32> {
+  (0..envelope.len())
+    .map(|i| {
+      let lo = i.saturating_sub(radius);
+      let hi = (i + radius + 1).min(envelope.len());
+      let slice = &envelope[lo..hi];
+      slice.iter().sum::<f32>() / slice.len() as f32
+    })
+    .collect()
+}
+
+fn detect_beats(envelope: &[f32], window_samples: usize, sample_rate: u32) -> Vec<f64> {
+  if envelope.len() < 3 {
+    return Vec::new();
+  }
+  let mean = envelope.iter().map(|&v| v as f64).sum::<f64>() / envelope.len() as f64;
+  let min_spacing_windows = ((BEAT_MIN_SPACING_SECS * sample_rate as f64)
+    / window_samples as f64)
+    .round()
+    .max(1.0) as usize;
+
+  let mut beats = Vec::new();
+  let mut last_idx: Option<usize> = None;
+  for i in 1..envelope.len() - 1 {
+    let v = envelope[i] as f64;
+    let is_local_max = v >= envelope[i - 1] as f64 && v >= envelope[i + 1] as f64;
+    let above_threshold = v > mean * BEAT_THRESHOLD_MULT;
+    let spaced = last_idx.map_or(true, |last| i - last >= min_spacing_windows);
+    if is_local_max && above_threshold && spaced {
+      beats.push((i * window_samples) as f64 / sample_rate as f64);
+      last_idx = Some(i);
+    }
+  }
+  beats
+}
+
+fn analyze_beats(input_path: &Path) -> Result<Vec<f64>, String> {
+  let samples = decode_mono_pcm(input_path)?;
+  let envelope = smooth_envelope(&rms_envelope(&samples, BEAT_WINDOW_SAMPLES), BEAT_SMOOTH_RADIUS);
+  Ok(detect_beats(&envelope, BEAT_WINDOW_SAMPLES, BEAT_SAMPLE_RATE))
+}
+
+fn beat_sync_filter(beats: &[f64]) -> Option<FilterSpec> {
+  if beats.is_empty() {
+    return None;
+  }
+  let windows = beats
+    .iter()
+    .map(|t| format!("between(t,{:.3},{:.3})", t, t + 0.15))
+    .collect::<Vec<_>>()
+    .join("+");
+  Some(FilterSpec {
+    name: "eq".to_string(),
+    args: vec![
+      ("saturation".to_string(), "1.4".to_string()),
+      ("enable".to_string(), format!("'{}'", windows)),
+    ],
+  })
+}
+
+const HIGHLIGHT_SPAN_SECS: f64 = 3.0;
+
+fn score_window(envelope: &[f32], window_samples: usize, sample_rate: u32, start: f64, end: f64) -> f64 {
+  let window_dur = window_samples as f64 / sample_rate as f64;
+  let lo = (start / window_dur).floor() as usize;
+  let hi = ((end / window_dur).ceil() as usize).min(envelope.len());
+  if lo >= hi {
+    return 0.0;
+  }
+  envelope[lo..hi].iter().map(|&v| v as f64).sum::<f64>() / (hi - lo) as f64
+}
+
+fn pick_highlight_windows(input_path: &Path, target_seconds: f64) -> Result<Vec<(f64, f64)>, String> {
+  let duration = video_duration_seconds(input_path)?;
+  let samples = decode_mono_pcm(input_path)?;
+  let envelope = rms_envelope(&samples, BEAT_WINDOW_SAMPLES);
+
+  let mut windows = Vec::new();
+  let mut t = 0.0;
+  while t < duration {
+    let end = (t + HIGHLIGHT_SPAN_SECS).min(duration);
+    if end - t < HIGHLIGHT_SPAN_SECS * 0.5 {
+      break;
+    }
+    let score = score_window(&envelope, BEAT_WINDOW_SAMPLES, BEAT_SAMPLE_RATE, t, end);
+    windows.push((t, end, score));
+    t += HIGHLIGHT_SPAN_SECS;
+  }
+
+  windows.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap_or(std::cmp::Ordering::Equal));
+  let mut selected = Vec::new();
+  let mut total = 0.0;
+  for w in windows {
+    if total >= target_seconds {
+      break;
+    }
+    total += w.1 - w.0;
+    selected.push(w);
+  }
+  selected.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+  Ok(selected.into_iter().map(|(start, end, _)| (start, end)).collect())
+}
+
 fn fallback_filters(prompt: &str) -> Vec<String> {
   let prompt = prompt.to_lowercase();
   if prompt.contains("energetic") || prompt.contains("fast") {
@@ -111,7 +551,38 @@ fn fallback_filters(prompt: &str) -> Vec<String> {
   }
 }
 
-fn gemini_filters(prompt: &str) -> Result<Vec<String>, String> {
+fn jittered(backoff: Duration) -> Duration {
+  let jitter = rand::random::<f64>() * 0.4 - 0.2;
+  let millis = (backoff.as_millis() as f64 * (1.0 + jitter)).max(0.0);
+  Duration::from_millis(millis as u64)
+}
+
+fn next_backoff(backoff: Duration) -> Duration {
+  (backoff * 2).min(GEMINI_BACKOFF_CAP)
+}
+
+fn parse_gemini_response(body: &[u8]) -> Result<Vec<String>, String> {
+  let response: GeminiResponse = serde_json::from_slice(body).map_err(|e| e.to_string())?;
+  let text = response
+    .candidates
+    .get(0)
+    .and_then(|c| c.content.parts.get(0))
+    .map(|p| p.text.clone())
+    .ok_or_else(|| "Gemini response missing text".to_string())?;
+
+  let json_value: serde_json::Value =
+    serde_json::from_str(&text).map_err(|e| e.to_string())?;
+  let filters = json_value["filters"]
+    .as_array()
+    .ok_or_else(|| "Gemini JSON missing filters".to_string())?
+    .iter()
+    .filter_map(|f| f.as_str().map(|s| s.to_string()))
+    .collect::<Vec<_>>();
+
+  Ok(filters)
+}
+
+async fn gemini_filters(prompt: &str) -> Result<Vec<String>, String> {
   let api_key =
     std::env::var("GEMINI_API_KEY").map_err(|_| "GEMINI_API_KEY not set".to_string())?;
   let request_body = serde_json::json!({
@@ -129,41 +600,57 @@ fn gemini_filters(prompt: &str) -> Result<Vec<String>, String> {
     ]
   });
 
-  let output = Command::new("curl")
-    .arg("-sS")
-    .arg("-H")
-    .arg("Content-Type: application/json")
-    .arg("-H")
-    .arg(format!("x-goog-api-key: {}", api_key))
-    .arg("-d")
-    .arg(request_body.to_string())
-    .arg("https://generativelanguage.googleapis.com/v1beta/models/gemini-2.5-flash:generateContent")
-    .output()
+  let client = reqwest::Client::builder()
+    .timeout(GEMINI_REQUEST_TIMEOUT)
+    .build()
     .map_err(|e| e.to_string())?;
 
-  if !output.status.success() {
-    return Err(String::from_utf8_lossy(&output.stderr).to_string());
-  }
+  let mut backoff = GEMINI_BACKOFF_BASE;
+  for attempt in 0..=GEMINI_MAX_RETRIES {
+    let result = client
+      .post(GEMINI_URL)
+      .header("Content-Type", "application/json")
+      .header("x-goog-api-key", &api_key)
+      .json(&request_body)
+      .send()
+      .await;
 
-  let response: GeminiResponse =
-    serde_json::from_slice(&output.stdout).map_err(|e| e.to_string())?;
-  let text = response
-    .candidates
-    .get(0)
-    .and_then(|c| c.content.parts.get(0))
-    .map(|p| p.text.clone())
-    .ok_or_else(|| "Gemini response missing text".to_string())?;
+    let resp = match result {
+      Ok(resp) => resp,
+      Err(e) => {
+        if attempt == GEMINI_MAX_RETRIES || !(e.is_timeout() || e.is_connect()) {
+          return Err(e.to_string());
+        }
+        tokio::time::sleep(jittered(backoff)).await;
+        backoff = next_backoff(backoff);
+        continue;
+      }
+    };
 
-  let json_value: serde_json::Value =
-    serde_json::from_str(&text).map_err(|e| e.to_string())?;
-  let filters = json_value["filters"]
-    .as_array()
-    .ok_or_else(|| "Gemini JSON missing filters".to_string())?
-    .iter()
-    .filter_map(|f| f.as_str().map(|s| s.to_string()))
-    .collect::<Vec<_>>();
+    let status = resp.status();
+    if status.is_success() {
+      let body = resp.bytes().await.map_err(|e| e.to_string())?;
+      return parse_gemini_response(&body);
+    }
 
-  Ok(filters)
+    let transient = status == reqwest::StatusCode::TOO_MANY_REQUESTS
+      || status == reqwest::StatusCode::SERVICE_UNAVAILABLE;
+    if !transient || attempt == GEMINI_MAX_RETRIES {
+      let body = resp.text().await.unwrap_or_default();
+      return Err(format!("Gemini request failed with {}: {}", status, body));
+    }
+
+    let retry_after = resp
+      .headers()
+      .get(reqwest::header::RETRY_AFTER)
+      .and_then(|v| v.to_str().ok())
+      .and_then(|s| s.parse::<u64>().ok())
+      .map(Duration::from_secs);
+    tokio::time::sleep(retry_after.unwrap_or_else(|| jittered(backoff))).await;
+    backoff = next_backoff(backoff);
+  }
+
+  unreachable!("loop always returns before exhausting retries")
 }
 
 async fn init_db(db_path: &PathBuf) -> Result<SqlitePool, sqlx::Error> {
@@ -196,6 +683,8 @@ async fn vibe_edit(
   prompt: String,
   license_key: Option<String>,
   add_overlay: Option<bool>,
+  beat_sync: Option<bool>,
+  window: Window,
   db: State<'_, Db>,
 ) -> Result<VibeEditResult, String> {
   let licensed = if let Some(key) = license_key {
@@ -204,7 +693,7 @@ async fn vibe_edit(
     false
   };
 
-  let (filters, used_gemini) = match gemini_filters(&prompt) {
+  let (filters, used_gemini) = match gemini_filters(&prompt).await {
     Ok(filters) => (filters, true),
     Err(_) => (fallback_filters(&prompt), false),
   };
@@ -219,6 +708,16 @@ async fn vibe_edit(
     }
   }
 
+  let mut filter_graph = sanitize_filters(filters);
+  if beat_sync.unwrap_or(false) {
+    if let Ok(beats) = analyze_beats(Path::new(&input_path)) {
+      if let Some(spec) = beat_sync_filter(&beats) {
+        filter_graph.push(spec);
+      }
+    }
+  }
+  let filters: Vec<String> = filter_graph.iter().map(render_filter_spec).collect();
+
   let input = std::path::PathBuf::from(&input_path);
   let output = input
     .with_file_name("vibe_output.mp4")
@@ -226,7 +725,9 @@ async fn vibe_edit(
     .to_string();
 
   let filter_desc = filters.join(",");
-  let ffmpeg_out = Command::new("ffmpeg")
+  let input_duration = video_duration_seconds(&input).unwrap_or(0.0);
+  let mut encode_cmd = Command::new("ffmpeg");
+  encode_cmd
     .arg("-y")
     .arg("-i")
     .arg(&input_path)
@@ -238,20 +739,8 @@ async fn vibe_edit(
     .arg("veryfast")
     .arg("-c:a")
     .arg("aac")
-    .arg(&output)
-    .output()
-    .map_err(|e| e.to_string())?;
-
-  if !ffmpeg_out.status.success() {
-    let stderr = String::from_utf8_lossy(&ffmpeg_out.stderr);
-    let msg = if stderr.is_empty() {
-      "FFmpeg failed (no stderr). Check ffmpeg is installed and path is valid."
-        .to_string()
-    } else {
-      format!("FFmpeg failed: {}", stderr.lines().take(5).collect::<Vec<_>>().join(" "))
-    };
-    return Err(msg);
-  }
+    .arg(&output);
+  spawn_ffmpeg_with_progress(encode_cmd, input_duration, &window, "encode")?;
 
   let mut final_output = output.clone();
 
@@ -265,6 +754,7 @@ async fn vibe_edit(
     let project_root = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
     let script = project_root.join("remotion").join("render.mjs");
     if script.exists() {
+      let _ = window.emit("vibe_edit_progress", VibeEditProgress { phase: "overlay".to_string(), percent: 0.0 });
       let node_out = Command::new("node")
         .arg(script)
         .arg(&output)
@@ -276,15 +766,18 @@ async fn vibe_edit(
       if node_out.status.success() {
         final_output = overlay_out;
       }
+      let _ = window.emit("vibe_edit_progress", VibeEditProgress { phase: "overlay".to_string(), percent: 100.0 });
     }
   }
 
+  let tags = derive_tags(&prompt, used_gemini, trial_watermark, run_overlay).join(",");
   sqlx::query(
-    "INSERT INTO projects (input_path, output_path, prompt) VALUES (?, ?, ?)",
+    "INSERT INTO projects (input_path, output_path, prompt, tags) VALUES (?, ?, ?, ?)",
   )
   .bind(&input_path)
   .bind(&final_output)
   .bind(&prompt)
+  .bind(&tags)
   .execute(&db.0)
   .await
   .map_err(|e| e.to_string())?;
@@ -292,11 +785,191 @@ async fn vibe_edit(
   Ok(VibeEditResult {
     output_path: final_output,
     filters,
+    filter_graph,
     used_gemini,
     trial_watermark,
   })
 }
 
+#[tauri::command]
+async fn extract_highlights(
+  input_path: String,
+  target_seconds: f64,
+  license_key: Option<String>,
+  db: State<'_, Db>,
+) -> Result<HighlightResult, String> {
+  let licensed = if let Some(key) = license_key {
+    is_license_valid(&key, &db.0).await?
+  } else {
+    false
+  };
+
+  let input = PathBuf::from(&input_path);
+  let segments = pick_highlight_windows(&input, target_seconds)?;
+  if segments.is_empty() {
+    return Err("no highlight segments found".to_string());
+  }
+
+  let segments_dir = input.with_file_name("vibe_highlights");
+  std::fs::create_dir_all(&segments_dir).map_err(|e| e.to_string())?;
+
+  let mut segment_paths = Vec::new();
+  for (i, (start, end)) in segments.iter().enumerate() {
+    let segment_path = segments_dir.join(format!("segment_{}.mp4", i));
+    let cut_out = Command::new("ffmpeg")
+      .arg("-y")
+      .arg("-ss")
+      .arg(format!("{:.3}", start))
+      .arg("-i")
+      .arg(&input_path)
+      .arg("-t")
+      .arg(format!("{:.3}", end - start))
+      .arg("-c:v")
+      .arg("libx264")
+      .arg("-preset")
+      .arg("veryfast")
+      .arg("-c:a")
+      .arg("aac")
+      .arg(&segment_path)
+      .output()
+      .map_err(|e| e.to_string())?;
+    if !cut_out.status.success() {
+      return Err(format!(
+        "FFmpeg failed cutting segment {}: {}",
+        i,
+        String::from_utf8_lossy(&cut_out.stderr)
+      ));
+    }
+    segment_paths.push(segment_path);
+  }
+
+  let concat_list = segments_dir.join("concat.txt");
+  let concat_contents = segment_paths
+    .iter()
+    .map(|p| format!("file '{}'", p.to_string_lossy()))
+    .collect::<Vec<_>>()
+    .join("\n");
+  std::fs::write(&concat_list, concat_contents).map_err(|e| e.to_string())?;
+
+  let output = input
+    .with_file_name("vibe_highlights.mp4")
+    .to_string_lossy()
+    .to_string();
+  let concat_out = Command::new("ffmpeg")
+    .arg("-y")
+    .arg("-f")
+    .arg("concat")
+    .arg("-safe")
+    .arg("0")
+    .arg("-i")
+    .arg(&concat_list)
+    .arg("-c")
+    .arg("copy")
+    .arg(&output)
+    .output()
+    .map_err(|e| e.to_string())?;
+  if !concat_out.status.success() {
+    return Err(format!(
+      "FFmpeg concat failed: {}",
+      String::from_utf8_lossy(&concat_out.stderr)
+    ));
+  }
+
+  let trial_watermark = !licensed;
+  let mut final_output = output.clone();
+  if trial_watermark {
+    let watermarked = input
+      .with_file_name("vibe_highlights_trial.mp4")
+      .to_string_lossy()
+      .to_string();
+    let wm_out = Command::new("ffmpeg")
+      .arg("-y")
+      .arg("-i")
+      .arg(&output)
+      .arg("-vf")
+      .arg(watermark_filter())
+      .arg("-c:v")
+      .arg("libx264")
+      .arg("-preset")
+      .arg("veryfast")
+      .arg("-c:a")
+      .arg("copy")
+      .arg(&watermarked)
+      .output()
+      .map_err(|e| e.to_string())?;
+    if wm_out.status.success() {
+      final_output = watermarked;
+    }
+  }
+
+  let prompt = format!("highlight_reel:{}s", target_seconds);
+  let mut tags = derive_tags(&prompt, false, trial_watermark, false);
+  tags.push("highlight".to_string());
+  sqlx::query(
+    "INSERT INTO projects (input_path, output_path, prompt, tags) VALUES (?, ?, ?, ?)",
+  )
+  .bind(&input_path)
+  .bind(&final_output)
+  .bind(&prompt)
+  .bind(tags.join(","))
+  .execute(&db.0)
+  .await
+  .map_err(|e| e.to_string())?;
+
+  Ok(HighlightResult {
+    output_path: final_output,
+    segments,
+    trial_watermark,
+  })
+}
+
+#[tauri::command]
+async fn list_projects(db: State<'_, Db>) -> Result<Vec<ProjectRecord>, String> {
+  sqlx::query_as::<_, ProjectRecord>(
+    "SELECT id, input_path, output_path, prompt, tags, created_at FROM projects ORDER BY id DESC",
+  )
+  .fetch_all(&db.0)
+  .await
+  .map_err(|e| e.to_string())
+}
+
+fn fts_phrase_query(raw: &str) -> String {
+  format!("\"{}\"", raw.replace('"', "\"\""))
+}
+
+#[tauri::command]
+async fn search_projects(
+  query: Option<String>,
+  tags: Option<String>,
+  db: State<'_, Db>,
+) -> Result<Vec<ProjectRecord>, String> {
+  let rows = match query.filter(|q| !q.trim().is_empty()) {
+    Some(q) => sqlx::query_as::<_, ProjectRecord>(
+      "SELECT p.id, p.input_path, p.output_path, p.prompt, p.tags, p.created_at \
+       FROM projects_fts f JOIN projects p ON p.id = f.rowid \
+       WHERE projects_fts MATCH ? ORDER BY rank",
+    )
+    .bind(fts_phrase_query(&q))
+    .fetch_all(&db.0)
+    .await
+    .map_err(|_| "search query could not be matched".to_string())?,
+    None => sqlx::query_as::<_, ProjectRecord>(
+      "SELECT id, input_path, output_path, prompt, tags, created_at FROM projects ORDER BY id DESC",
+    )
+    .fetch_all(&db.0)
+    .await
+    .map_err(|e| e.to_string())?,
+  };
+
+  Ok(match tags.filter(|t| !t.trim().is_empty()) {
+    Some(tag) => rows
+      .into_iter()
+      .filter(|p| p.tags.split(',').any(|t| t == tag))
+      .collect(),
+    None => rows,
+  })
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
   tauri::Builder::default()
@@ -322,7 +995,144 @@ pub fn run() {
       Ok(())
     })
     .plugin(tauri_plugin_dialog::init())
-    .invoke_handler(tauri::generate_handler![vibe_edit, check_license])
+    .invoke_handler(tauri::generate_handler![
+      vibe_edit,
+      check_license,
+      extract_highlights,
+      list_projects,
+      search_projects
+    ])
     .run(tauri::generate_context!())
     .expect("error while running tauri application");
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn jittered_stays_within_plus_minus_20_percent() {
+    let base = Duration::from_millis(500);
+    for _ in 0..50 {
+      let d = jittered(base);
+      assert!(d.as_millis() >= 400 && d.as_millis() <= 600);
+    }
+  }
+
+  #[test]
+  fn backoff_doubles_and_caps() {
+    assert_eq!(next_backoff(GEMINI_BACKOFF_BASE), GEMINI_BACKOFF_BASE * 2);
+    let mut backoff = GEMINI_BACKOFF_BASE;
+    for _ in 0..10 {
+      backoff = next_backoff(backoff);
+    }
+    assert_eq!(backoff, GEMINI_BACKOFF_CAP);
+  }
+
+  fn sanitize(raw: &str) -> Vec<String> {
+    sanitize_filters(vec![raw.to_string()])
+      .iter()
+      .map(render_filter_spec)
+      .collect()
+  }
+
+  #[test]
+  fn drawtext_with_colon_in_quoted_text_survives_round_trip() {
+    let rendered = sanitize("drawtext=fontfile=FreeSerif.ttf:text='VIBE: ENERGETIC':x=16:y=16:fontsize=24:fontcolor=white");
+    assert_eq!(rendered.len(), 1);
+    assert!(rendered[0].contains("ENERGETIC"));
+  }
+
+  #[test]
+  fn comma_and_equals_are_stripped_from_free_form_args() {
+    let rendered = sanitize("drawtext=text=x,scale=99999:99999");
+    assert_eq!(rendered.len(), 1);
+    assert!(!rendered[0].contains(','));
+    assert!(!rendered[0].contains("scale="));
+  }
+
+  #[test]
+  fn unknown_filter_name_is_rejected() {
+    let graph = sanitize_filters(vec!["lavfi_shell=cmd=rm".to_string()]);
+    assert_eq!(graph.len(), 1);
+    assert_eq!(graph[0].name, "hue");
+  }
+
+  #[test]
+  fn numeric_args_are_clamped() {
+    let rendered = sanitize("hue=s=999");
+    assert_eq!(rendered, vec!["hue=s=3".to_string()]);
+  }
+
+  #[test]
+  fn detect_beats_finds_spaced_local_maxima() {
+    let envelope = vec![0.1, 0.1, 0.9, 0.1, 0.1, 0.1, 0.9, 0.1, 0.1];
+    let beats = detect_beats(&envelope, BEAT_WINDOW_SAMPLES, BEAT_SAMPLE_RATE);
+    assert_eq!(beats.len(), 2);
+  }
+
+  #[test]
+  fn detect_beats_ignores_adjacent_non_maximal_spike() {
+    let envelope = vec![0.1, 0.9, 0.85, 0.1, 0.1];
+    let beats = detect_beats(&envelope, BEAT_WINDOW_SAMPLES, BEAT_SAMPLE_RATE);
+    assert_eq!(beats.len(), 1);
+  }
+
+  #[test]
+  fn score_window_averages_envelope_inside_range() {
+    let window_samples = 1000;
+    let sample_rate = 1000;
+    let envelope = vec![0.0, 1.0, 1.0, 0.0];
+    let score = score_window(&envelope, window_samples, sample_rate, 1.0, 3.0);
+    assert!((score - 1.0).abs() < 1e-9);
+  }
+
+  #[test]
+  fn derive_tags_classifies_prompt_and_pipeline_state() {
+    let tags = derive_tags("make it energetic", true, true, true);
+    assert!(tags.contains(&"energetic".to_string()));
+    assert!(tags.contains(&"overlay".to_string()));
+    assert!(tags.contains(&"watermarked".to_string()));
+    assert!(tags.contains(&"gemini".to_string()));
+    assert!(!tags.contains(&"fallback".to_string()));
+  }
+
+  #[test]
+  fn derive_tags_falls_back_when_gemini_unused() {
+    let tags = derive_tags("chill vibes please", false, false, false);
+    assert!(tags.contains(&"chill".to_string()));
+    assert!(tags.contains(&"fallback".to_string()));
+    assert!(!tags.contains(&"watermarked".to_string()));
+  }
+
+  #[test]
+  fn fts_phrase_query_wraps_fts5_operator_chars_as_a_literal_phrase() {
+    assert_eq!(fts_phrase_query("fast-paced"), "\"fast-paced\"");
+    assert_eq!(fts_phrase_query("col:value"), "\"col:value\"");
+  }
+
+  #[test]
+  fn fts_phrase_query_escapes_embedded_quotes() {
+    assert_eq!(fts_phrase_query("say \"hi\""), "\"say \"\"hi\"\"\"");
+  }
+
+  #[test]
+  fn out_time_percent_at_start_is_zero() {
+    assert_eq!(out_time_percent(0.0, 10.0), Some(0.0));
+  }
+
+  #[test]
+  fn out_time_percent_at_full_duration_is_100() {
+    assert_eq!(out_time_percent(10_000_000.0, 10.0), Some(100.0));
+  }
+
+  #[test]
+  fn out_time_percent_past_full_duration_is_clamped_to_100() {
+    assert_eq!(out_time_percent(15_000_000.0, 10.0), Some(100.0));
+  }
+
+  #[test]
+  fn out_time_percent_is_none_for_zero_duration() {
+    assert_eq!(out_time_percent(5_000_000.0, 0.0), None);
+  }
+}